@@ -1,6 +1,7 @@
 use std::boxed::Box;
 use std::ffi::CStr;
 use std::os::raw::{c_char, c_int};
+use std::str::Utf8Error;
 use std::{fmt, mem, ptr, slice};
 
 #[macro_use]
@@ -12,6 +13,7 @@ use utils::{set_panic_hook, LAST_ERROR};
 pub enum ErrorKind {
     Panic(String),
     CrfError(crfsuite::CrfError),
+    InvalidUtf8(Utf8Error),
 }
 
 pub type Result<T> = ::std::result::Result<T, ErrorKind>;
@@ -23,6 +25,7 @@ impl fmt::Display for ErrorKind {
         match *self {
             ErrorKind::Panic(ref err) => err.fmt(f),
             ErrorKind::CrfError(ref err) => err.fmt(f),
+            ErrorKind::InvalidUtf8(ref err) => err.fmt(f),
         }
     }
 }
@@ -33,6 +36,17 @@ impl From<crfsuite::CrfError> for ErrorKind {
     }
 }
 
+impl From<Utf8Error> for ErrorKind {
+    fn from(err: Utf8Error) -> ErrorKind {
+        ErrorKind::InvalidUtf8(err)
+    }
+}
+
+/// Read a C string as UTF-8, as a recoverable [`ErrorKind::InvalidUtf8`] instead of panicking.
+unsafe fn cstr_to_str<'a>(s: *const c_char) -> Result<&'a str> {
+    Ok(CStr::from_ptr(s).to_str()?)
+}
+
 /// Represents a string.
 #[repr(C)]
 pub struct FfiStr {
@@ -88,7 +102,7 @@ ffi_fn! {
     /// to make sure you are not freeing the memory or you need to set the
     /// owned flag to false.
     unsafe fn pycrfsuite_str_from_cstr(s: *const c_char) -> Result<FfiStr> {
-        let s = CStr::from_ptr(s).to_str().unwrap();
+        let s = cstr_to_str(s)?;
         Ok(FfiStr {
             data: s.as_ptr() as *mut _,
             len: s.len(),
@@ -113,6 +127,7 @@ pub enum CrfErrorCode {
     NoError = 0,
     Panic = 1,
     CrfError = 2,
+    InvalidUtf8 = 3,
 }
 
 impl CrfErrorCode {
@@ -120,6 +135,7 @@ impl CrfErrorCode {
         match *kind {
             ErrorKind::Panic(_) => CrfErrorCode::Panic,
             ErrorKind::CrfError(_) => CrfErrorCode::CrfError,
+            ErrorKind::InvalidUtf8(_) => CrfErrorCode::InvalidUtf8,
         }
     }
 }
@@ -179,8 +195,7 @@ pub struct Trainer;
 
 ffi_fn! {
     unsafe fn pycrfsuite_model_open(s: *const c_char) -> Result<*mut Model> {
-        let path_cstr = CStr::from_ptr(s);
-        let model = crfsuite::Model::from_file(path_cstr.to_str().unwrap())?;
+        let model = crfsuite::Model::from_file(cstr_to_str(s)?)?;
         Ok(Box::into_raw(Box::new(model)) as *mut Model)
     }
 }
@@ -189,7 +204,7 @@ ffi_fn! {
 ffi_fn! {
     unsafe fn pycrfsuite_model_dump(m: *mut Model, fd: c_int) -> Result<()> {
         let model = m as *mut crfsuite::Model;
-        Ok((*model).dump(fd)?)
+        Ok((*model).dump_fd(fd)?)
     }
 }
 
@@ -198,7 +213,7 @@ ffi_fn! {
     unsafe fn pycrfsuite_model_dump(m: *mut Model, fd: c_int) -> Result<()> {
         let model = m as *mut crfsuite::Model;
         let handle = libc::get_osfhandle(fd);
-        Ok((*model).dump(handle as _)?)
+        Ok((*model).dump_fd(handle as _)?)
     }
 }
 
@@ -219,6 +234,56 @@ ffi_fn! {
     }
 }
 
+ffi_fn! {
+    unsafe fn pycrfsuite_model_labels(m: *mut Model) -> Result<*mut Tags> {
+        let model = m as *mut crfsuite::Model;
+        let labels = (*model).labels()?;
+        let mut tags: Vec<FfiStr> = labels.into_iter()
+            .map(FfiStr::from_string)
+            .collect();
+        tags.shrink_to_fit();
+        let tag_count = tags.len();
+        let buffer = tags.as_mut_ptr();
+        mem::forget(tags);
+        let c_tags = Tags { data: buffer, len: tag_count };
+        Ok(Box::into_raw(Box::new(c_tags)))
+    }
+}
+
+ffi_fn! {
+    unsafe fn pycrfsuite_model_attributes(m: *mut Model) -> Result<*mut Tags> {
+        let model = m as *mut crfsuite::Model;
+        let attrs = (*model).attributes()?;
+        let mut tags: Vec<FfiStr> = attrs.into_iter()
+            .map(FfiStr::from_string)
+            .collect();
+        tags.shrink_to_fit();
+        let tag_count = tags.len();
+        let buffer = tags.as_mut_ptr();
+        mem::forget(tags);
+        let c_tags = Tags { data: buffer, len: tag_count };
+        Ok(Box::into_raw(Box::new(c_tags)))
+    }
+}
+
+ffi_fn! {
+    unsafe fn pycrfsuite_model_state_weight(m: *mut Model, attr: *const c_char, label: *const c_char) -> Result<f64> {
+        let model = m as *mut crfsuite::Model;
+        let attr_str = cstr_to_str(attr)?;
+        let label_str = cstr_to_str(label)?;
+        Ok((*model).state_weight(attr_str, label_str)?)
+    }
+}
+
+ffi_fn! {
+    unsafe fn pycrfsuite_model_transition_weight(m: *mut Model, from: *const c_char, to: *const c_char) -> Result<f64> {
+        let model = m as *mut crfsuite::Model;
+        let from_str = cstr_to_str(from)?;
+        let to_str = cstr_to_str(to)?;
+        Ok((*model).transition_weight(from_str, to_str)?)
+    }
+}
+
 ffi_fn! {
     unsafe fn pycrfsuite_tagger_create(m: *mut Model) -> Result<*mut Tagger> {
         let model = m as *mut crfsuite::Model;
@@ -291,6 +356,169 @@ ffi_fn! {
     }
 }
 
+#[repr(C)]
+#[derive(Debug)]
+pub struct Sequence {
+    pub items: *const AttributeList,
+    pub len: usize,
+}
+
+#[repr(C)]
+#[derive(Debug)]
+pub struct TagsBatch {
+    pub data: *mut Tags,
+    pub len: usize,
+}
+
+ffi_fn! {
+    unsafe fn pycrfsuite_tagsbatch_destroy(batch: *mut TagsBatch) {
+        if !batch.is_null() {
+            let entries = Vec::from_raw_parts((*batch).data, (*batch).len, (*batch).len);
+            for entry in entries {
+                Vec::from_raw_parts(entry.data, entry.len, entry.len);
+            }
+            Box::from_raw(batch);
+        }
+    }
+}
+
+ffi_fn! {
+    unsafe fn pycrfsuite_tagger_tag_batch(t: *mut Tagger, seqs: *const Sequence, seqs_len: usize) -> Result<*mut TagsBatch> {
+        let tagger = t as *mut crfsuite::Tagger;
+        let sequences = slice::from_raw_parts(seqs, seqs_len);
+        let mut results: Vec<Tags> = Vec::with_capacity(sequences.len());
+        // Reused across sequences instead of allocating a fresh outer `Vec` per call.
+        let mut x: Vec<crfsuite::Item> = Vec::new();
+        for seq in sequences {
+            x.clear();
+            let items = slice::from_raw_parts(seq.items, seq.len);
+            for item in items {
+                let attr_slice = slice::from_raw_parts(item.data, item.len);
+                let attrs: Vec<crfsuite::Attribute> = attr_slice.iter()
+                    .map(|attr| crfsuite::Attribute::new(CStr::from_ptr(attr.name).to_string_lossy().to_owned(), attr.value))
+                    .collect();
+                x.push(attrs);
+            }
+            let labels = (*tagger).tag(&x)?;
+            let mut tags: Vec<FfiStr> = labels.into_iter()
+                .map(FfiStr::from_string)
+                .collect();
+            tags.shrink_to_fit();
+            let tag_count = tags.len();
+            let buffer = tags.as_mut_ptr();
+            mem::forget(tags);
+            results.push(Tags { data: buffer, len: tag_count });
+        }
+        results.shrink_to_fit();
+        let batch_len = results.len();
+        let buffer = results.as_mut_ptr();
+        mem::forget(results);
+        let batch = TagsBatch { data: buffer, len: batch_len };
+        Ok(Box::into_raw(Box::new(batch)))
+    }
+}
+
+#[repr(C)]
+#[derive(Debug)]
+pub struct ScoredTags {
+    pub tags: Tags,
+    pub probability: f64,
+}
+
+#[repr(C)]
+#[derive(Debug)]
+pub struct TagsList {
+    pub data: *mut ScoredTags,
+    pub len: usize,
+}
+
+ffi_fn! {
+    unsafe fn pycrfsuite_tagslist_destroy(list: *mut TagsList) {
+        if !list.is_null() {
+            let entries = Vec::from_raw_parts((*list).data, (*list).len, (*list).len);
+            for entry in entries {
+                Vec::from_raw_parts(entry.tags.data, entry.tags.len, entry.tags.len);
+            }
+            Box::from_raw(list);
+        }
+    }
+}
+
+ffi_fn! {
+    unsafe fn pycrfsuite_tagger_tag_nbest(t: *mut Tagger, xseq: *const AttributeList, xseq_len: usize, n: usize) -> Result<*mut TagsList> {
+        let items = slice::from_raw_parts(xseq, xseq_len);
+        let mut x = Vec::with_capacity(items.len());
+        for item in items {
+            let attr_slice = slice::from_raw_parts(item.data, item.len);
+            let attrs: Vec<crfsuite::Attribute> = attr_slice.iter()
+                .map(|attr| crfsuite::Attribute::new(CStr::from_ptr(attr.name).to_string_lossy().to_owned(), attr.value))
+                .collect();
+            x.push(attrs);
+        }
+        let tagger = t as *mut crfsuite::Tagger;
+        let results = (*tagger).tag_nbest(&x, n)?;
+        let mut scored: Vec<ScoredTags> = results.into_iter()
+            .map(|(labels, probability)| {
+                let mut tags: Vec<FfiStr> = labels.into_iter()
+                    .map(FfiStr::from_string)
+                    .collect();
+                tags.shrink_to_fit();
+                let tag_count = tags.len();
+                let buffer = tags.as_mut_ptr();
+                mem::forget(tags);
+                ScoredTags { tags: Tags { data: buffer, len: tag_count }, probability }
+            })
+            .collect();
+        scored.shrink_to_fit();
+        let list_len = scored.len();
+        let buffer = scored.as_mut_ptr();
+        mem::forget(scored);
+        let list = TagsList { data: buffer, len: list_len };
+        Ok(Box::into_raw(Box::new(list)))
+    }
+}
+
+ffi_fn! {
+    unsafe fn pycrfsuite_tagger_probability(t: *mut Tagger, xseq: *const AttributeList, xseq_len: usize, yseq: *const *const c_char, yseq_len: usize) -> Result<f64> {
+        let items = slice::from_raw_parts(xseq, xseq_len);
+        let mut x = Vec::with_capacity(items.len());
+        for item in items {
+            let attr_slice = slice::from_raw_parts(item.data, item.len);
+            let attrs: Vec<crfsuite::Attribute> = attr_slice.iter()
+                .map(|attr| crfsuite::Attribute::new(CStr::from_ptr(attr.name).to_string_lossy().to_owned(), attr.value))
+                .collect();
+            x.push(attrs);
+        }
+        let items = slice::from_raw_parts(yseq, yseq_len);
+        let mut y = Vec::with_capacity(items.len());
+        for item in items {
+            let tag = cstr_to_str(*item)?;
+            y.push(tag);
+        }
+        let tagger = t as *mut crfsuite::Tagger;
+        (*tagger).set(&x)?;
+        Ok((*tagger).probability(&y)?)
+    }
+}
+
+ffi_fn! {
+    unsafe fn pycrfsuite_tagger_marginal(t: *mut Tagger, xseq: *const AttributeList, xseq_len: usize, label: *const c_char, position: c_int) -> Result<f64> {
+        let items = slice::from_raw_parts(xseq, xseq_len);
+        let mut x = Vec::with_capacity(items.len());
+        for item in items {
+            let attr_slice = slice::from_raw_parts(item.data, item.len);
+            let attrs: Vec<crfsuite::Attribute> = attr_slice.iter()
+                .map(|attr| crfsuite::Attribute::new(CStr::from_ptr(attr.name).to_string_lossy().to_owned(), attr.value))
+                .collect();
+            x.push(attrs);
+        }
+        let label_str = cstr_to_str(label)?;
+        let tagger = t as *mut crfsuite::Tagger;
+        (*tagger).set(&x)?;
+        Ok((*tagger).marginal(label_str, position as i32)?)
+    }
+}
+
 ffi_fn! {
     unsafe fn pycrfsuite_trainer_create(verbose: bool) -> Result<*mut Trainer> {
         let trainer = crfsuite::Trainer::new(verbose);
@@ -309,10 +537,7 @@ ffi_fn! {
 
 ffi_fn! {
     unsafe fn pycrfsuite_trainer_select(trainer: *mut Trainer, algo: *const c_char) -> Result<()> {
-        let algorithm = CStr::from_ptr(algo)
-            .to_str()
-            .unwrap()
-            .parse::<crfsuite::Algorithm>()?;
+        let algorithm = cstr_to_str(algo)?.parse::<crfsuite::Algorithm>()?;
         let trainer = trainer as *mut crfsuite::Trainer;
         Ok((*trainer).select(algorithm, crfsuite::GraphicalModel::CRF1D)?)
     }
@@ -328,7 +553,7 @@ ffi_fn! {
 ffi_fn! {
     unsafe fn pycrfsuite_trainer_train(trainer: *mut Trainer, model: *const c_char, holdout: c_int) -> Result<()> {
         let trainer = trainer as *mut crfsuite::Trainer;
-        let model_str = CStr::from_ptr(model).to_str().unwrap();
+        let model_str = cstr_to_str(model)?;
         Ok((*trainer).train(model_str, holdout as i32)?)
     }
 }
@@ -348,7 +573,7 @@ ffi_fn! {
         let items = slice::from_raw_parts(yseq, yseq_len);
         let mut y = Vec::with_capacity(items.len());
         for item in items {
-            let tag = CStr::from_ptr(*item).to_str().unwrap();
+            let tag = cstr_to_str(*item)?;
             y.push(tag);
         }
         Ok((*trainer).append(&x, &y, group)?)
@@ -358,8 +583,8 @@ ffi_fn! {
 ffi_fn! {
     unsafe fn pycrfsuite_trainer_set(trainer: *mut Trainer, name: *const c_char, value: *const c_char) -> Result<()> {
         let trainer = trainer as *mut crfsuite::Trainer;
-        let name_str = CStr::from_ptr(name).to_str().unwrap();
-        let value_str = CStr::from_ptr(value).to_str().unwrap();
+        let name_str = cstr_to_str(name)?;
+        let value_str = cstr_to_str(value)?;
         Ok((*trainer).set(name_str, value_str)?)
     }
 }
@@ -367,7 +592,7 @@ ffi_fn! {
 ffi_fn! {
     unsafe fn pycrfsuite_trainer_get(trainer: *mut Trainer, name: *const c_char) -> Result<FfiStr> {
         let trainer = trainer as *mut crfsuite::Trainer;
-        let name_str = CStr::from_ptr(name).to_str().unwrap();
+        let name_str = cstr_to_str(name)?;
         let value = (*trainer).get(name_str)?;
         Ok(FfiStr::from_string(value))
     }
@@ -376,7 +601,7 @@ ffi_fn! {
 ffi_fn! {
     unsafe fn pycrfsuite_trainer_help(trainer: *mut Trainer, name: *const c_char) -> Result<FfiStr> {
         let trainer = trainer as *mut crfsuite::Trainer;
-        let name_str = CStr::from_ptr(name).to_str().unwrap();
+        let name_str = cstr_to_str(name)?;
         let value = (*trainer).help(name_str)?;
         Ok(FfiStr::from_string(value))
     }
@@ -11,15 +11,59 @@ fn fail_on_empty_directory(name: &str) {
     }
 }
 
+/// Map a Rust/Cargo arch name to the `CMAKE_OSX_ARCHITECTURES` value CMake expects.
+fn osx_arch(target_arch: &str) -> &str {
+    match target_arch {
+        "aarch64" => "arm64",
+        other => other,
+    }
+}
+
+fn configure_apple(cfg: &mut cmake::Config, target_arch: &str) {
+    // A universal2 build is requested by setting CRFSUITE_SYS_UNIVERSAL2=1; otherwise we build
+    // a single-arch static lib for whatever triple Cargo asked for.
+    if std::env::var("CRFSUITE_SYS_UNIVERSAL2").is_ok() {
+        cfg.define("CMAKE_OSX_ARCHITECTURES", "arm64;x86_64");
+    } else {
+        cfg.define("CMAKE_OSX_ARCHITECTURES", osx_arch(target_arch));
+    }
+}
+
+fn configure_cross(cfg: &mut cmake::Config, target_os: &str, target_arch: &str) {
+    let system_name = match target_os {
+        "linux" => "Linux",
+        "windows" => "Windows",
+        "android" => "Android",
+        other => other,
+    };
+    cfg.define("CMAKE_SYSTEM_NAME", system_name);
+    cfg.define("CMAKE_SYSTEM_PROCESSOR", target_arch);
+
+    // Respect the same `CC_<target>` override cc-rs honors, falling back to the conventional
+    // `<target>-gcc` cross-compiler name so CMake doesn't default to the host compiler.
+    let target = std::env::var("TARGET").unwrap();
+    if let Ok(compiler) = std::env::var(format!("CC_{}", target.replace('-', "_"))) {
+        cfg.define("CMAKE_C_COMPILER", compiler);
+    } else {
+        cfg.define("CMAKE_C_COMPILER", format!("{}-gcc", target));
+    }
+}
+
 fn build_crfsuite() {
     let mut cfg = cmake::Config::new("");
     cfg.register_dep("cqdb").register_dep("lbfgs");
-    if cfg!(target_os = "macos") {
-        let target_arch = std::env::var("CARGO_CFG_TARGET_ARCH").unwrap();
-        if target_arch == "x86_64" {
-            cfg.define("CMAKE_OSX_ARCHITECTURES", "x86_64");
-        }
+
+    let host = std::env::var("HOST").unwrap();
+    let target = std::env::var("TARGET").unwrap();
+    let target_os = std::env::var("CARGO_CFG_TARGET_OS").unwrap();
+    let target_arch = std::env::var("CARGO_CFG_TARGET_ARCH").unwrap();
+
+    if target_os == "macos" {
+        configure_apple(&mut cfg, &target_arch);
+    } else if target != host {
+        configure_cross(&mut cfg, &target_os, &target_arch);
     }
+
     let dst = cfg.build();
     println!("cargo:rustc-link-search=native={}/lib", dst.display());
     println!("cargo:rustc-link-lib=static=cqdb");
@@ -1,40 +1,76 @@
-#![feature(proc_macro, specialization, const_fn)]
-extern crate pyo3;
-extern crate crfsuite;
-
+use pyo3::exceptions::PyValueError;
 use pyo3::prelude::*;
 
-#[py::class]
+#[pyclass]
 struct Model {
-    inner: crfsuite::Model
+    inner: crfsuite::Model,
 }
 
-#[py::methods]
+#[pymethods]
 impl Model {
-
     #[new]
-    fn __new__(obj: &PyRawObject, path: String) -> PyResult<()> {
-        obj.init(|t| Model { inner: crfsuite::Model::from_file(&path).unwrap() })
+    fn new(path: String) -> PyResult<Self> {
+        let inner = crfsuite::Model::from_file(&path).map_err(to_py_err)?;
+        Ok(Model { inner })
     }
 
     fn tag(&self, py: Python, items: Vec<Vec<(String, f64)>>) -> PyResult<Vec<String>> {
-       let ret = py.allow_threads(move || {
-            let mut attrs = Vec::with_capacity(items.len());
-            for item in &items {
-                let seq: Vec<crfsuite::Attribute> = item.iter().map(|x| crfsuite::Attribute::new(x.0.to_string(), x.1)).collect();
-                attrs.push(seq);
-            }
-            let mut tagger = self.inner.tagger().unwrap();
-            tagger.tag(&attrs).unwrap()
-        });
-       Ok(ret)
+        py.allow_threads(move || {
+            let xseq = items_to_xseq(items);
+            let mut tagger = self.inner.tagger().map_err(to_py_err)?;
+            tagger.tag(&xseq).map_err(to_py_err)
+        })
+    }
+
+    /// Probability of the predicted label sequence for `items`.
+    fn probability(&self, py: Python, items: Vec<Vec<(String, f64)>>) -> PyResult<f64> {
+        py.allow_threads(move || {
+            let xseq = items_to_xseq(items);
+            let mut tagger = self.inner.tagger().map_err(to_py_err)?;
+            let yseq = tagger.tag(&xseq).map_err(to_py_err)?;
+            tagger.probability(&yseq).map_err(to_py_err)
+        })
+    }
+
+    /// Predicted labels for `items`, each paired with its marginal probability.
+    fn tag_marginals(
+        &self,
+        py: Python,
+        items: Vec<Vec<(String, f64)>>,
+    ) -> PyResult<Vec<(String, f64)>> {
+        py.allow_threads(move || {
+            let xseq = items_to_xseq(items);
+            let mut tagger = self.inner.tagger().map_err(to_py_err)?;
+            let yseq = tagger.tag(&xseq).map_err(to_py_err)?;
+            yseq.into_iter()
+                .enumerate()
+                .map(|(i, label)| {
+                    let prob = tagger.marginal(&label, i as i32).map_err(to_py_err)?;
+                    Ok((label, prob))
+                })
+                .collect()
+        })
     }
 }
 
+fn items_to_xseq(items: Vec<Vec<(String, f64)>>) -> Vec<crfsuite::Item> {
+    items
+        .into_iter()
+        .map(|item| {
+            item.into_iter()
+                .map(|(name, value)| crfsuite::Attribute::new(name, value))
+                .collect()
+        })
+        .collect()
+}
+
+fn to_py_err(err: crfsuite::CrfError) -> PyErr {
+    PyValueError::new_err(err.to_string())
+}
+
 /// crfsuite
-#[py::modinit(_crfsuite)]
-fn init_module(py: Python, m: &PyModule) -> PyResult<()> {
+#[pymodule]
+fn _crfsuite(_py: Python, m: &PyModule) -> PyResult<()> {
     m.add_class::<Model>()?;
-
     Ok(())
 }
@@ -0,0 +1,260 @@
+//! k-fold cross-validation and sequence-labeling evaluation metrics.
+//!
+//! [`CrossValidator`] assigns appended instances to `k` folds, trains one model per fold with
+//! that fold held out using [`Trainer`]'s `group`/`holdout` support, tags the held-out instances,
+//! and accumulates the results into an [`Evaluation`].
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use crate::{Algorithm, CrfError, GraphicalModel, Item, Model, Result, Trainer};
+
+/// True/false positive and false negative counts for a single label, and the precision,
+/// recall and F1 derived from them.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct LabelScore {
+    pub true_positives: u64,
+    pub false_positives: u64,
+    pub false_negatives: u64,
+}
+
+impl LabelScore {
+    /// TP / (TP + FP), or 0 if the label was never predicted.
+    pub fn precision(&self) -> f64 {
+        let predicted = self.true_positives + self.false_positives;
+        if predicted == 0 {
+            0.0
+        } else {
+            self.true_positives as f64 / predicted as f64
+        }
+    }
+
+    /// TP / (TP + FN), or 0 if the label never occurs in the gold data.
+    pub fn recall(&self) -> f64 {
+        let gold = self.true_positives + self.false_negatives;
+        if gold == 0 {
+            0.0
+        } else {
+            self.true_positives as f64 / gold as f64
+        }
+    }
+
+    /// 2 * P * R / (P + R), or 0 if both precision and recall are 0.
+    pub fn f1(&self) -> f64 {
+        let (p, r) = (self.precision(), self.recall());
+        if p + r == 0.0 {
+            0.0
+        } else {
+            2.0 * p * r / (p + r)
+        }
+    }
+}
+
+/// A sequence-labeling evaluation report: per-label precision/recall/F1, plus item-level and
+/// instance-level accuracy over every held-out prediction.
+#[derive(Debug, Clone, Default)]
+pub struct Evaluation {
+    /// Per-label true/false positive and false negative counts.
+    pub labels: HashMap<String, LabelScore>,
+    // Label excluded from `macro_average`, e.g. an "outside" tag like `"O"`.
+    outside_label: Option<String>,
+    correct_items: u64,
+    total_items: u64,
+    correct_instances: u64,
+    total_instances: u64,
+}
+
+impl Evaluation {
+    /// Record one predicted/gold sequence pair.
+    fn record<T: AsRef<str>>(&mut self, predicted: &[String], gold: &[T]) {
+        self.total_instances += 1;
+        let mut instance_correct = true;
+        for (p, g) in predicted.iter().zip(gold) {
+            let g = g.as_ref();
+            self.total_items += 1;
+            if p == g {
+                self.correct_items += 1;
+                self.labels.entry(p.to_string()).or_default().true_positives += 1;
+            } else {
+                instance_correct = false;
+                self.labels.entry(p.to_string()).or_default().false_positives += 1;
+                self.labels.entry(g.to_string()).or_default().false_negatives += 1;
+            }
+        }
+        if instance_correct {
+            self.correct_instances += 1;
+        }
+    }
+
+    /// Fraction of item positions where the predicted label matches the gold label.
+    pub fn item_accuracy(&self) -> f64 {
+        if self.total_items == 0 {
+            0.0
+        } else {
+            self.correct_items as f64 / self.total_items as f64
+        }
+    }
+
+    /// Fraction of instances that were entirely correctly labeled.
+    pub fn instance_accuracy(&self) -> f64 {
+        if self.total_instances == 0 {
+            0.0
+        } else {
+            self.correct_instances as f64 / self.total_instances as f64
+        }
+    }
+
+    /// Macro-averaged precision, recall and F1 across every label except the configured
+    /// outside label.
+    pub fn macro_average(&self) -> (f64, f64, f64) {
+        let scores: Vec<&LabelScore> = self
+            .labels
+            .iter()
+            .filter(|(label, _)| Some(label.as_str()) != self.outside_label.as_deref())
+            .map(|(_, score)| score)
+            .collect();
+        if scores.is_empty() {
+            return (0.0, 0.0, 0.0);
+        }
+        let n = scores.len() as f64;
+        let precision = scores.iter().map(|s| s.precision()).sum::<f64>() / n;
+        let recall = scores.iter().map(|s| s.recall()).sum::<f64>() / n;
+        let f1 = scores.iter().map(|s| s.f1()).sum::<f64>() / n;
+        (precision, recall, f1)
+    }
+}
+
+/// Runs k-fold cross-validation for sequence labeling, producing an [`Evaluation`] over every
+/// held-out prediction.
+pub struct CrossValidator {
+    folds: usize,
+    algorithm: Algorithm,
+    graphical_model: GraphicalModel,
+    outside_label: Option<String>,
+    instances: Vec<(Vec<Item>, Vec<String>, usize)>,
+}
+
+impl CrossValidator {
+    /// Create a validator that splits appended instances into `folds` folds and trains each
+    /// fold's model with `algorithm`.
+    ///
+    /// `folds` must be at least 2, since a single fold would hold out all of its own training
+    /// data.
+    pub fn new(folds: usize, algorithm: Algorithm) -> Result<Self> {
+        if folds < 2 {
+            return Err(CrfError::ValueError(format!(
+                "CrossValidator requires at least 2 folds, got {}",
+                folds
+            )));
+        }
+        Ok(Self {
+            folds,
+            algorithm,
+            graphical_model: GraphicalModel::CRF1D,
+            outside_label: None,
+            instances: Vec::new(),
+        })
+    }
+
+    /// Exclude `label` (e.g. an "outside" tag like `"O"`) from the per-label averages in the
+    /// resulting [`Evaluation`].
+    pub fn outside_label<T: Into<String>>(mut self, label: T) -> Self {
+        self.outside_label = Some(label.into());
+        self
+    }
+
+    /// Append an instance to the data set; it is assigned to one of the `folds` folds in
+    /// round-robin order.
+    pub fn append<T: AsRef<str>>(&mut self, xseq: &[Item], yseq: &[T]) {
+        let fold = self.instances.len() % self.folds;
+        let yseq = yseq.iter().map(|y| y.as_ref().to_string()).collect();
+        self.instances.push((xseq.to_vec(), yseq, fold));
+    }
+
+    /// Train one model per fold with that fold held out, tag the held-out instances, and
+    /// return the combined evaluation over all folds.
+    pub fn run(&self) -> Result<Evaluation> {
+        let mut evaluation = Evaluation {
+            outside_label: self.outside_label.clone(),
+            ..Evaluation::default()
+        };
+        for holdout in 0..self.folds {
+            let mut trainer = Trainer::default();
+            trainer.select(self.algorithm, self.graphical_model)?;
+            for (xseq, yseq, fold) in &self.instances {
+                trainer.append(xseq, yseq, *fold as i32)?;
+            }
+
+            static COUNTER: AtomicU64 = AtomicU64::new(0);
+            let mut model_path = std::env::temp_dir();
+            model_path.push(format!(
+                "crfsuite-cv-{}-{}-{}.model",
+                std::process::id(),
+                COUNTER.fetch_add(1, Ordering::Relaxed),
+                holdout
+            ));
+            trainer.train(model_path.to_str().unwrap(), holdout as i32)?;
+            drop(trainer);
+
+            let model = Model::from_file(model_path.to_str().unwrap())?;
+            let _ = std::fs::remove_file(&model_path);
+            let mut tagger = model.tagger()?;
+            for (xseq, yseq, fold) in &self.instances {
+                if *fold != holdout {
+                    continue;
+                }
+                let predicted = tagger.tag(xseq)?;
+                evaluation.record(&predicted, yseq);
+            }
+        }
+        Ok(evaluation)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CrossValidator, Evaluation, LabelScore};
+    use crate::Algorithm;
+
+    #[test]
+    fn test_label_score_precision_recall_f1() {
+        let score = LabelScore {
+            true_positives: 6,
+            false_positives: 2,
+            false_negatives: 2,
+        };
+        assert_eq!(score.precision(), 0.75);
+        assert_eq!(score.recall(), 0.75);
+        assert_eq!(score.f1(), 0.75);
+    }
+
+    #[test]
+    fn test_label_score_never_predicted_or_never_gold() {
+        let score = LabelScore::default();
+        assert_eq!(score.precision(), 0.0);
+        assert_eq!(score.recall(), 0.0);
+        assert_eq!(score.f1(), 0.0);
+    }
+
+    #[test]
+    fn test_evaluation_record_and_macro_average() {
+        let mut evaluation = Evaluation::default();
+        evaluation.record(
+            &["a".to_string(), "b".to_string()],
+            &["a".to_string(), "a".to_string()],
+        );
+        assert_eq!(evaluation.item_accuracy(), 0.5);
+        assert_eq!(evaluation.instance_accuracy(), 0.0);
+
+        let (precision, recall, _f1) = evaluation.macro_average();
+        assert!(precision > 0.0 && precision < 1.0);
+        assert!(recall > 0.0 && recall < 1.0);
+    }
+
+    #[test]
+    fn test_cross_validator_rejects_fewer_than_two_folds() {
+        assert!(CrossValidator::new(0, Algorithm::LBFGS).is_err());
+        assert!(CrossValidator::new(1, Algorithm::LBFGS).is_err());
+        assert!(CrossValidator::new(2, Algorithm::LBFGS).is_ok());
+    }
+}
@@ -0,0 +1,271 @@
+//! Typed ingestion of raw tabular data into [`Item`]s.
+//!
+//! A [`FeatureExtractor`] holds a fixed per-column [`Conversion`] schema and turns a row of
+//! `&str` columns (e.g. from a CSV/TSV reader) into the `Vec<Attribute>`-backed `Item` that
+//! [`Trainer::append`](crate::Trainer::append) and [`Tagger::tag`](crate::Tagger::tag) expect.
+
+use crate::{Attribute, CrfError, Item, Result};
+
+/// How a single column's raw string value is converted into attribute(s).
+#[derive(Debug, Clone, PartialEq)]
+pub enum Conversion {
+    /// Keep the value as-is and emit a categorical attribute `"column=value"` with weight 1.0.
+    Categorical,
+    /// Parse the value as an integer and emit `"column"` with the parsed value.
+    Integer,
+    /// Parse the value as a float and emit `"column"` with the parsed value.
+    Float,
+    /// Parse the value as a boolean (`true`/`false`, `1`/`0`, `yes`/`no`) and emit `"column"`
+    /// with weight 1.0 when true; false values emit nothing, matching a presence/absence
+    /// attribute.
+    Boolean,
+    /// Parse the value with the given `strftime`-style format (supporting `%Y`, `%m`, `%d`,
+    /// `%H`, `%M`, `%S`) and expand it into cyclic components, `"column_hour=N"` and
+    /// `"column_weekday=N"` (Monday = 0).
+    Timestamp(String),
+}
+
+/// An alias for [`Conversion::Categorical`], for call sites that want to spell out that a
+/// column is passed through unchanged.
+pub const AS_IS: Conversion = Conversion::Categorical;
+
+impl Conversion {
+    fn convert(&self, name: &str, value: &str, item: &mut Item) -> Result<()> {
+        match self {
+            Conversion::Categorical => {
+                item.push(Attribute::new(format!("{}={}", name, value), 1.0));
+            }
+            Conversion::Integer => {
+                let parsed: i64 = value.parse().map_err(|_| {
+                    CrfError::ValueError(format!(
+                        "Invalid integer for column `{}`: `{}`",
+                        name, value
+                    ))
+                })?;
+                item.push(Attribute::new(name.to_string(), parsed as f64));
+            }
+            Conversion::Float => {
+                let parsed: f64 = value.parse().map_err(|_| {
+                    CrfError::ValueError(format!(
+                        "Invalid float for column `{}`: `{}`",
+                        name, value
+                    ))
+                })?;
+                item.push(Attribute::new(name.to_string(), parsed));
+            }
+            Conversion::Boolean => {
+                let parsed = parse_bool(value).ok_or_else(|| {
+                    CrfError::ValueError(format!(
+                        "Invalid boolean for column `{}`: `{}`",
+                        name, value
+                    ))
+                })?;
+                if parsed {
+                    item.push(Attribute::new(name.to_string(), 1.0));
+                }
+            }
+            Conversion::Timestamp(fmt) => {
+                let ts = parse_timestamp(fmt, value).map_err(|err| {
+                    CrfError::ValueError(format!(
+                        "Invalid timestamp for column `{}`: {}",
+                        name, err
+                    ))
+                })?;
+                item.push(Attribute::new(format!("{}_hour={}", name, ts.hour), 1.0));
+                item.push(Attribute::new(
+                    format!("{}_weekday={}", name, ts.weekday),
+                    1.0,
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+fn parse_bool(value: &str) -> Option<bool> {
+    match value.to_ascii_lowercase().as_str() {
+        "true" | "1" | "yes" => Some(true),
+        "false" | "0" | "no" => Some(false),
+        _ => None,
+    }
+}
+
+/// The pieces of a parsed timestamp that feed the cyclic attributes.
+struct ParsedTimestamp {
+    hour: u32,
+    weekday: u32,
+}
+
+/// A minimal `strptime`-like parser supporting `%Y`, `%m`, `%d`, `%H`, `%M` and `%S`.
+fn parse_timestamp(fmt: &str, value: &str) -> ::std::result::Result<ParsedTimestamp, String> {
+    let mut year: i64 = 1970;
+    let mut month: u32 = 1;
+    let mut day: u32 = 1;
+    let mut hour: u32 = 0;
+
+    let mut fmt_chars = fmt.chars().peekable();
+    let mut rest = value;
+    while let Some(c) = fmt_chars.next() {
+        if c == '%' {
+            let spec = fmt_chars
+                .next()
+                .ok_or_else(|| "dangling `%` in format".to_string())?;
+            let width = match spec {
+                'Y' => 4,
+                'm' | 'd' | 'H' | 'M' | 'S' => 2,
+                other => return Err(format!("unsupported format specifier `%{}`", other)),
+            };
+            if rest.len() < width {
+                return Err(format!("`{}` is too short for `{}`", value, fmt));
+            }
+            let (digits, remainder) = rest.split_at(width);
+            let parsed: u32 = digits
+                .parse()
+                .map_err(|_| format!("expected digits, found `{}`", digits))?;
+            match spec {
+                'Y' => year = parsed as i64,
+                'm' => month = parsed,
+                'd' => day = parsed,
+                'H' => hour = parsed,
+                'M' | 'S' => {}
+                _ => unreachable!(),
+            }
+            rest = remainder;
+        } else {
+            let actual = rest
+                .chars()
+                .next()
+                .ok_or_else(|| format!("`{}` ended before `{}`", value, fmt))?;
+            if actual != c {
+                return Err(format!("expected `{}`, found `{}` in `{}`", c, actual, value));
+            }
+            rest = &rest[actual.len_utf8()..];
+        }
+    }
+
+    Ok(ParsedTimestamp {
+        hour,
+        weekday: weekday_from_civil(year, month, day),
+    })
+}
+
+/// Days since the Unix epoch for a given Gregorian date, via Howard Hinnant's
+/// `days_from_civil` algorithm.
+fn days_from_civil(y: i64, m: u32, d: u32) -> i64 {
+    let y = if m <= 2 { y - 1 } else { y };
+    let era = if y >= 0 { y } else { y - 399 } / 400;
+    let yoe = y - era * 400;
+    let mp = (m as i64 + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + d as i64 - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146097 + doe - 719468
+}
+
+/// Day of week for a Gregorian date, Monday = 0.
+fn weekday_from_civil(y: i64, m: u32, d: u32) -> u32 {
+    let days = days_from_civil(y, m, d);
+    // 1970-01-01 was a Thursday, i.e. weekday 3 when Monday = 0.
+    (days + 3).rem_euclid(7) as u32
+}
+
+/// Builds [`Item`]s out of raw tabular rows using a fixed per-column schema.
+///
+/// ```no_run
+/// use crfsuite::features::{Conversion, FeatureExtractor};
+///
+/// let extractor = FeatureExtractor::new()
+///     .column("word", Conversion::Categorical)
+///     .column("length", Conversion::Integer);
+/// let item = extractor.extract(&["dog", "3"]).unwrap();
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct FeatureExtractor {
+    columns: Vec<(String, Conversion)>,
+}
+
+impl FeatureExtractor {
+    /// Create an extractor with no columns.
+    pub fn new() -> Self {
+        Self {
+            columns: Vec::new(),
+        }
+    }
+
+    /// Append a column to the schema.
+    pub fn column<T: Into<String>>(mut self, name: T, conversion: Conversion) -> Self {
+        self.columns.push((name.into(), conversion));
+        self
+    }
+
+    /// Convert a row of raw column values into an `Item`, according to the schema.
+    pub fn extract<T: AsRef<str>>(&self, row: &[T]) -> Result<Item> {
+        if row.len() != self.columns.len() {
+            return Err(CrfError::ValueError(format!(
+                "Expected {} columns, got {}",
+                self.columns.len(),
+                row.len()
+            )));
+        }
+        let mut item = Item::new();
+        for ((name, conversion), value) in self.columns.iter().zip(row) {
+            conversion.convert(name, value.as_ref(), &mut item)?;
+        }
+        Ok(item)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{days_from_civil, parse_bool, parse_timestamp, weekday_from_civil, Conversion, FeatureExtractor};
+
+    #[test]
+    fn test_days_from_civil_epoch() {
+        assert_eq!(days_from_civil(1970, 1, 1), 0);
+        assert_eq!(days_from_civil(1970, 1, 2), 1);
+        assert_eq!(days_from_civil(1969, 12, 31), -1);
+    }
+
+    #[test]
+    fn test_weekday_from_civil() {
+        // 1970-01-01 was a Thursday, i.e. weekday 3 when Monday = 0.
+        assert_eq!(weekday_from_civil(1970, 1, 1), 3);
+        // 2024-01-01 was a Monday.
+        assert_eq!(weekday_from_civil(2024, 1, 1), 0);
+    }
+
+    #[test]
+    fn test_parse_bool() {
+        assert_eq!(parse_bool("true"), Some(true));
+        assert_eq!(parse_bool("1"), Some(true));
+        assert_eq!(parse_bool("yes"), Some(true));
+        assert_eq!(parse_bool("false"), Some(false));
+        assert_eq!(parse_bool("0"), Some(false));
+        assert_eq!(parse_bool("no"), Some(false));
+        assert_eq!(parse_bool("maybe"), None);
+    }
+
+    #[test]
+    fn test_parse_timestamp() {
+        let ts = parse_timestamp("%Y-%m-%d %H:%M:%S", "2024-01-01 13:30:00").unwrap();
+        assert_eq!(ts.hour, 13);
+        assert_eq!(ts.weekday, 0);
+
+        assert!(parse_timestamp("%Y-%m-%d", "not-a-date").is_err());
+        assert!(parse_timestamp("%Y-%m-%d", "2024-01").is_err());
+    }
+
+    #[test]
+    fn test_feature_extractor_extract() {
+        let extractor = FeatureExtractor::new()
+            .column("word", Conversion::Categorical)
+            .column("length", Conversion::Integer)
+            .column("is_capitalized", Conversion::Boolean);
+        let item = extractor.extract(&["Dog", "3", "true"]).unwrap();
+        assert_eq!(item.len(), 3);
+
+        assert!(extractor.extract(&["Dog", "3"]).is_err());
+        assert!(extractor
+            .extract(&["Dog", "not-a-number", "true"])
+            .is_err());
+    }
+}
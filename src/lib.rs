@@ -2,21 +2,27 @@
 #![allow(clippy::useless_transmute)]
 #![allow(clippy::transmute_ptr_to_ref)]
 #![allow(clippy::transmute_ptr_to_ptr)]
+use std::collections::HashMap;
 use std::ffi::{CStr, CString};
 use std::fs::File;
-use std::io::{Cursor, Read, Seek, SeekFrom};
+use std::io::{Cursor, Read, Seek, SeekFrom, Write};
 #[cfg(unix)]
 use std::os::unix::io::{IntoRawFd, RawFd};
 #[cfg(windows)]
 use std::os::windows::io::{IntoRawHandle, RawHandle};
 use std::path::Path;
-use std::{error, fmt, mem, ptr, slice};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::{error, fmt, mem, ptr, slice, thread};
 
 use crfsuite_sys::*;
 #[cfg(not(windows))]
 use libc::{c_char, c_int, c_uint};
 use libc::{c_void, fclose, fdopen};
 
+pub mod eval;
+pub mod features;
+
 /// Errors from crfsuite ffi functions
 #[derive(Debug, Clone, PartialEq)]
 pub enum CrfSuiteError {
@@ -725,13 +731,13 @@ impl Model {
     }
 
     #[cfg(unix)]
-    /// Print the model in human-readable format
+    /// Print the model in human-readable format to a raw file descriptor
     ///
     /// ## Parameters
     ///
     /// `file`: Something convertable to file descriptor
     ///
-    pub fn dump(&self, fd: RawFd) -> Result<()> {
+    pub fn dump_fd(&self, fd: RawFd) -> Result<()> {
         let c_mode = CString::new("w").unwrap();
         unsafe {
             let file = fdopen(fd, c_mode.as_ptr());
@@ -748,13 +754,13 @@ impl Model {
     }
 
     #[cfg(windows)]
-    /// Print the model in human-readable format
+    /// Print the model in human-readable format to a raw file handle
     ///
     /// ## Parameters
     ///
     /// `file`: Something convertable to file descriptor
     ///
-    pub fn dump(&self, fd: RawHandle) -> Result<()> {
+    pub fn dump_fd(&self, fd: RawHandle) -> Result<()> {
         unsafe {
             let fd = libc::open_osfhandle(fd as _, libc::O_RDWR);
             if fd == -1 {
@@ -783,7 +789,7 @@ impl Model {
     ///
     pub fn dump_file<T: AsRef<Path>>(&self, path: T) -> Result<()> {
         let file = File::create(path).expect("create file failed");
-        self.dump(file.into_raw_fd())
+        self.dump_fd(file.into_raw_fd())
     }
 
     #[cfg(windows)]
@@ -795,7 +801,99 @@ impl Model {
     ///
     pub fn dump_file<T: AsRef<Path>>(&self, path: T) -> Result<()> {
         let file = File::create(path).expect("create file failed");
-        self.dump(file.into_raw_handle())
+        self.dump_fd(file.into_raw_handle())
+    }
+
+    /// Print the model in human-readable format, returning it as a `String`.
+    ///
+    /// Unlike [`Model::dump_file`] this works the same way on every platform: callers never
+    /// have to manage a raw file descriptor/handle themselves.
+    pub fn dump(&self) -> Result<String> {
+        self.dump_to_string()
+    }
+
+    /// Print the model in human-readable format to any [`std::io::Write`].
+    ///
+    /// The dump is written to a private temporary file and copied into `writer`, since the
+    /// underlying crfsuite model only knows how to dump to a `FILE*`.
+    pub fn dump_to<W: Write>(&self, mut writer: W) -> Result<()> {
+        static COUNTER: AtomicU64 = AtomicU64::new(0);
+        let mut path = std::env::temp_dir();
+        path.push(format!(
+            "crfsuite-dump-{}-{}.txt",
+            std::process::id(),
+            COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+        self.dump_file(&path)?;
+        let bytes = std::fs::read(&path).map_err(|err| {
+            CrfError::InvalidModel(format!("Failed to read model dump: {}", err))
+        })?;
+        let _ = std::fs::remove_file(&path);
+        writer.write_all(&bytes).map_err(|err| {
+            CrfError::InvalidModel(format!("Failed to write model dump: {}", err))
+        })?;
+        Ok(())
+    }
+
+    /// Print the model in human-readable format, returning it as a `String`.
+    pub fn dump_to_string(&self) -> Result<String> {
+        let mut buf = Vec::new();
+        self.dump_to(&mut buf)?;
+        String::from_utf8(buf)
+            .map_err(|err| CrfError::InvalidModel(format!("Model dump was not valid UTF-8: {}", err)))
+    }
+
+    /// Obtain the list of labels known to the model.
+    pub fn labels(&self) -> Result<Vec<String>> {
+        unsafe { dictionary_to_vec(self.get_labels()?) }
+    }
+
+    /// Obtain the list of attributes known to the model.
+    pub fn attributes(&self) -> Result<Vec<String>> {
+        unsafe { dictionary_to_vec(self.get_attrs()?) }
+    }
+
+    /// Look up the weight of the state feature connecting `attr` to `label`.
+    pub fn state_weight(&self, attr: &str, label: &str) -> Result<f64> {
+        self.info()?
+            .state_features
+            .get(&(attr.to_string(), label.to_string()))
+            .copied()
+            .ok_or_else(|| {
+                CrfError::ValueError(format!(
+                    "No state feature from attribute `{}` to label `{}`",
+                    attr, label
+                ))
+            })
+    }
+
+    /// Look up the weight of the transition feature from label `from` to label `to`.
+    pub fn transition_weight(&self, from: &str, to: &str) -> Result<f64> {
+        self.info()?
+            .transitions
+            .get(&(from.to_string(), to.to_string()))
+            .copied()
+            .ok_or_else(|| {
+                CrfError::ValueError(format!(
+                    "No transition feature from label `{}` to label `{}`",
+                    from, to
+                ))
+            })
+    }
+
+    /// Parse the model's dump into structured introspection data: its label/attribute
+    /// dictionaries and the learned state/transition feature weights.
+    ///
+    /// The crfsuite model interface does not expose feature weights directly, so this scans
+    /// the textual `TRANSITIONS`/`STATE_FEATURES` sections of [`Model::dump`].
+    pub fn info(&self) -> Result<ModelInfo> {
+        let dump = self.dump()?;
+        Ok(ModelInfo {
+            attributes: self.attributes()?,
+            labels: self.labels()?,
+            state_features: parse_dump_pairs(&dump, "STATE_FEATURES", "-->"),
+            transitions: parse_dump_pairs(&dump, "TRANSITIONS", "-->"),
+        })
     }
 
     unsafe fn get_attrs(&self) -> Result<*mut crfsuite_dictionary_t> {
@@ -818,6 +916,128 @@ impl Model {
         }
         Ok(labels)
     }
+
+    /// Tag every sequence in `sequences`, spreading the work across a thread per CPU core.
+    ///
+    /// `Model` is `Send + Sync` and opening a [`Tagger`] from it is cheap, so each worker
+    /// thread gets its own tagger instead of needing one `Model` per request.
+    pub fn tag_batch(&self, sequences: &[Vec<Item>]) -> Result<Vec<Vec<String>>> {
+        tag_batch_with(self, sequences)
+    }
+}
+
+/// Shared implementation behind [`Model::tag_batch`] and [`TaggerPool::tag_batch`].
+fn tag_batch_with(model: &Model, sequences: &[Vec<Item>]) -> Result<Vec<Vec<String>>> {
+    if sequences.is_empty() {
+        return Ok(Vec::new());
+    }
+    let num_workers = thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(1)
+        .min(sequences.len());
+    let chunk_size = (sequences.len() + num_workers - 1) / num_workers;
+
+    thread::scope(|scope| {
+        let handles: Vec<_> = sequences
+            .chunks(chunk_size)
+            .map(|chunk| {
+                scope.spawn(move || -> Result<Vec<Vec<String>>> {
+                    let mut tagger = model.tagger()?;
+                    chunk.iter().map(|xseq| tagger.tag(xseq)).collect()
+                })
+            })
+            .collect();
+
+        let mut results = Vec::with_capacity(sequences.len());
+        for handle in handles {
+            let chunk_result = handle.join().expect("tagging worker thread panicked")?;
+            results.extend(chunk_result);
+        }
+        Ok(results)
+    })
+}
+
+/// Read every entry out of a crfsuite dictionary (labels or attributes) as owned strings.
+unsafe fn dictionary_to_vec(dict: *mut crfsuite_dictionary_t) -> Result<Vec<String>> {
+    let length = (*dict).num.map(|f| f(dict)).unwrap();
+    let mut values = Vec::with_capacity(length as usize);
+    for i in 0..length {
+        let mut value: *mut libc::c_char = ptr::null_mut();
+        let ret = (*dict)
+            .to_string
+            .map(|f| f(dict, i, &mut value as *mut *mut _ as *mut *const _))
+            .unwrap();
+        if ret != 0 {
+            (*dict).release.map(|f| f(dict)).unwrap();
+            return Err(CrfError::CrfSuiteError(CrfSuiteError::from(ret)));
+        }
+        values.push(CStr::from_ptr(value).to_string_lossy().into_owned());
+        (*dict).free.map(|f| f(dict, value)).unwrap();
+    }
+    (*dict).release.map(|f| f(dict)).unwrap();
+    Ok(values)
+}
+
+/// Structured introspection data parsed out of a model's dump: its label/attribute
+/// dictionaries and the learned state/transition feature weights.
+#[derive(Debug, Clone, Default)]
+pub struct ModelInfo {
+    /// Every attribute name known to the model.
+    pub attributes: Vec<String>,
+    /// Every label known to the model.
+    pub labels: Vec<String>,
+    /// State feature weights, keyed by `(attribute, label)`.
+    pub state_features: HashMap<(String, String), f64>,
+    /// Transition feature weights, keyed by `(label_from, label_to)`.
+    pub transitions: HashMap<(String, String), f64>,
+}
+
+/// Parse every `left <arrow> right: weight` line out of a model dump's `section` (e.g.
+/// `STATE_FEATURES`/`TRANSITIONS`) into a `(left, right) -> weight` map.
+fn parse_dump_pairs(dump: &str, section: &str, arrow: &str) -> HashMap<(String, String), f64> {
+    let mut pairs = HashMap::new();
+    let header = format!("{} = {{", section);
+    let body = match dump.split(&header).nth(1).and_then(|rest| rest.split('}').next()) {
+        Some(body) => body,
+        None => return pairs,
+    };
+    for line in body.lines() {
+        let parsed = line.rsplit_once(':').and_then(|(pair, weight)| {
+            let (lhs, rhs) = pair.split_once(arrow)?;
+            let lhs = strip_dump_index(lhs.trim()).to_string();
+            let rhs = rhs.trim().to_string();
+            let weight: f64 = weight.trim().parse().ok()?;
+            Some((lhs, rhs, weight))
+        });
+        if let Some((lhs, rhs, weight)) = parsed {
+            pairs.insert((lhs, rhs), weight);
+        }
+    }
+    pairs
+}
+
+/// Strip a dump line's leading `(N)` feature index, e.g. `"(12) 1gram=foo"` -> `"1gram=foo"`.
+///
+/// Only strips a well-formed `(digits)` prefix followed by whitespace, so attribute/label
+/// names that themselves start with a digit, space or paren (e.g. `"1gram=foo"`) are left
+/// untouched.
+fn strip_dump_index(s: &str) -> &str {
+    let rest = match s.strip_prefix('(') {
+        Some(rest) => rest,
+        None => return s,
+    };
+    let digits_end = match rest.find(')') {
+        Some(idx) => idx,
+        None => return s,
+    };
+    if digits_end == 0 || !rest[..digits_end].bytes().all(|b| b.is_ascii_digit()) {
+        return s;
+    }
+    let tail = &rest[digits_end + 1..];
+    if !tail.starts_with(char::is_whitespace) {
+        return s;
+    }
+    tail.trim_start()
 }
 
 impl Drop for Model {
@@ -868,8 +1088,13 @@ impl<'a> Tagger<'a> {
         self.viterbi()
     }
 
-    /// Set an item sequence.
-    fn set(&mut self, xseq: &[Item]) -> Result<()> {
+    /// Load an item sequence into the tagger without running inference.
+    ///
+    /// This is the building block behind [`Tagger::tag`], but it is also useful on its own:
+    /// after `set`, [`Tagger::viterbi`], [`Tagger::probability`] and [`Tagger::marginal`] all
+    /// operate on the loaded sequence, so scoring an alternative labeling no longer requires
+    /// re-tagging.
+    pub fn set(&mut self, xseq: &[Item]) -> Result<()> {
         unsafe {
             let attrs = self.model.get_attrs()?;
             let xseq_len = xseq.len();
@@ -1053,11 +1278,203 @@ impl<'a> Tagger<'a> {
             Ok(prob)
         }
     }
+
+    /// The marginal probability of every label at every position of the currently loaded
+    /// instance.
+    ///
+    /// Equivalent to calling [`Tagger::marginal`] for every `(label, position)` pair, but
+    /// fetches the label dictionary once instead of once per call, and groups the results per
+    /// position (each position's probabilities sum to ~1.0).
+    pub fn marginal_distribution(&self) -> Result<Vec<Vec<(String, f64)>>> {
+        unsafe {
+            let length = (*self.tagger).length.map(|f| f(self.tagger)).unwrap() as usize;
+            if length == 0 {
+                return Ok(Vec::new());
+            }
+            let labels = self.model.get_labels()?;
+            let num_labels = (*labels).num.map(|f| f(labels)).unwrap();
+            let mut label_names = Vec::with_capacity(num_labels as usize);
+            for label_id in 0..num_labels {
+                let mut name: *mut libc::c_char = ptr::null_mut();
+                let ret = (*labels)
+                    .to_string
+                    .map(|f| f(labels, label_id, &mut name as *mut *mut _ as *mut *const _))
+                    .unwrap();
+                if ret != 0 {
+                    (*labels).release.map(|f| f(labels)).unwrap();
+                    return Err(CrfError::CrfSuiteError(CrfSuiteError::from(ret)));
+                }
+                label_names.push(CStr::from_ptr(name).to_string_lossy().into_owned());
+                (*labels).free.map(|f| f(labels, name)).unwrap();
+            }
+
+            let mut distribution = Vec::with_capacity(length);
+            for position in 0..length as i32 {
+                let mut row = Vec::with_capacity(label_names.len());
+                for (label_id, name) in label_names.iter().enumerate() {
+                    let mut prob: floatval_t = 0.0;
+                    let ret = (*self.tagger)
+                        .marginal_point
+                        .map(|f| f(self.tagger, label_id as i32, position, &mut prob))
+                        .unwrap();
+                    if ret != 0 {
+                        (*labels).release.map(|f| f(labels)).unwrap();
+                        return Err(CrfError::CrfSuiteError(CrfSuiteError::from(ret)));
+                    }
+                    row.push((name.clone(), prob));
+                }
+                distribution.push(row);
+            }
+            (*labels).release.map(|f| f(labels)).unwrap();
+            Ok(distribution)
+        }
+    }
+
+    /// Compute the log partition function for the currently loaded instance.
+    fn lognorm(&self) -> Result<f64> {
+        let mut lognorm: floatval_t = 0.0;
+        unsafe {
+            let ret = (*self.tagger)
+                .lognorm
+                .map(|f| f(self.tagger, &mut lognorm))
+                .unwrap();
+            if ret != 0 {
+                return Err(CrfError::CrfSuiteError(CrfSuiteError::from(ret)));
+            }
+        }
+        Ok(lognorm)
+    }
+
+    /// Find the `n` most probable label sequences for the item sequence, each with its
+    /// probability.
+    ///
+    /// The underlying C tagger has no n-best decoding (its `viterbi` finds only the single
+    /// best path), so this is built on top of the structured state/transition weights from [`Model::info`]: for each
+    /// position and label it keeps the `n` best-scoring partial paths (the standard list
+    /// Viterbi algorithm), then backtracks from the `n` best complete paths at the final
+    /// position. Each path's score is converted to a probability via `exp(score - lognorm)`.
+    pub fn tag_nbest(&mut self, xseq: &[Item], n: usize) -> Result<Vec<(Vec<String>, f64)>> {
+        if xseq.is_empty() || n == 0 {
+            return Ok(Vec::new());
+        }
+        self.set(xseq)?;
+        let lognorm = self.lognorm()?;
+        let info = self.model.info()?;
+        let labels = &info.labels;
+
+        let emission = |t: usize, label: &str| -> f64 {
+            xseq[t]
+                .iter()
+                .filter_map(|attr| {
+                    info.state_features
+                        .get(&(attr.name.clone(), label.to_string()))
+                        .map(|w| w * attr.value)
+                })
+                .sum()
+        };
+
+        #[derive(Clone, Copy)]
+        struct NBestEntry {
+            score: f64,
+            // (label index, slot index) of the predecessor in the previous position's list.
+            prev: Option<(usize, usize)>,
+        }
+
+        // `table[t][label_index]` holds up to `n` candidate entries for being in
+        // `label_index` at position `t`, sorted best-first.
+        let mut table: Vec<Vec<Vec<NBestEntry>>> = Vec::with_capacity(xseq.len());
+
+        for t in 0..xseq.len() {
+            let mut column = Vec::with_capacity(labels.len());
+            for (label_index, label) in labels.iter().enumerate() {
+                let emit = emission(t, label);
+                let mut candidates: Vec<NBestEntry> = if t == 0 {
+                    vec![NBestEntry {
+                        score: emit,
+                        prev: None,
+                    }]
+                } else {
+                    let mut candidates = Vec::new();
+                    for (prev_label_index, prev_label) in labels.iter().enumerate() {
+                        let transition = info
+                            .transitions
+                            .get(&(prev_label.clone(), label.clone()))
+                            .copied()
+                            .unwrap_or(0.0);
+                        for (slot, prev_entry) in table[t - 1][prev_label_index].iter().enumerate()
+                        {
+                            candidates.push(NBestEntry {
+                                score: prev_entry.score + transition + emit,
+                                prev: Some((prev_label_index, slot)),
+                            });
+                        }
+                    }
+                    candidates
+                };
+                candidates.sort_by(|a, b| {
+                    b.score
+                        .partial_cmp(&a.score)
+                        .unwrap_or(std::cmp::Ordering::Equal)
+                });
+                candidates.truncate(n);
+                column.push(candidates);
+            }
+            table.push(column);
+        }
+
+        let last = xseq.len() - 1;
+        let mut finals: Vec<(usize, usize, f64)> = Vec::new();
+        for (label_index, column) in table[last].iter().enumerate() {
+            for (slot, entry) in column.iter().enumerate() {
+                finals.push((label_index, slot, entry.score));
+            }
+        }
+        finals.sort_by(|a, b| b.2.partial_cmp(&a.2).unwrap_or(std::cmp::Ordering::Equal));
+        finals.truncate(n);
+
+        let mut results = Vec::with_capacity(finals.len());
+        for (label_index, slot, score) in finals {
+            let mut path = Vec::with_capacity(xseq.len());
+            let mut cur_label = label_index;
+            let mut cur_slot = slot;
+            for t in (0..=last).rev() {
+                path.push(labels[cur_label].clone());
+                if let Some((prev_label, prev_slot)) = table[t][cur_label][cur_slot].prev {
+                    cur_label = prev_label;
+                    cur_slot = prev_slot;
+                }
+            }
+            path.reverse();
+            results.push((path, (score - lognorm).exp()));
+        }
+        Ok(results)
+    }
+}
+
+/// A pool of taggers built from a shared, read-only [`Model`], for tagging many sequences
+/// concurrently.
+///
+/// Each worker thread gets its own [`Tagger`] built from the same `Arc<Model>`, since a
+/// `Tagger` holds mutable decoding state and can only process one sequence at a time.
+pub struct TaggerPool {
+    model: Arc<Model>,
+}
+
+impl TaggerPool {
+    /// Build a pool backed by `model`.
+    pub fn new(model: Arc<Model>) -> Self {
+        Self { model }
+    }
+
+    /// Tag every sequence in `sequences`, spreading the work across a thread per CPU core.
+    pub fn tag_batch(&self, sequences: &[Vec<Item>]) -> Result<Vec<Vec<String>>> {
+        tag_batch_with(&self.model, sequences)
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{Algorithm, Attribute, GraphicalModel, Result};
+    use super::{parse_dump_pairs, Algorithm, Attribute, GraphicalModel, Result};
 
     #[test]
     fn test_str_to_algorithm_enum() {
@@ -1110,4 +1527,26 @@ mod tests {
         Attribute::from(("foo", 1.0));
         assert_eq!(Attribute::from("foo"), Attribute::from(("foo", 1.0)));
     }
+
+    #[test]
+    fn test_parse_dump_pairs_state_features_use_long_arrow() {
+        // crfsuite's dump routine writes both sections with the same `-->` delimiter.
+        let dump = "STATE_FEATURES = {\n    (0) word.lower=the --> sunny: 0.695315\n}\n\
+                    TRANSITIONS = {\n    (0) sunny --> rainy: -0.355313\n}\n";
+        let state_features = parse_dump_pairs(dump, "STATE_FEATURES", "-->");
+        assert_eq!(
+            state_features.get(&("word.lower=the".to_string(), "sunny".to_string())),
+            Some(&0.695315)
+        );
+    }
+
+    #[test]
+    fn test_parse_dump_pairs_preserves_digit_prefixed_names() {
+        let dump = "STATE_FEATURES = {\n    (12) 1gram=foo --> sunny: 0.5\n}\n";
+        let state_features = parse_dump_pairs(dump, "STATE_FEATURES", "-->");
+        assert_eq!(
+            state_features.get(&("1gram=foo".to_string(), "sunny".to_string())),
+            Some(&0.5)
+        );
+    }
 }
@@ -0,0 +1,29 @@
+extern crate crfsuite;
+
+use crfsuite::eval::CrossValidator;
+use crfsuite::{Algorithm, Attribute};
+
+#[test]
+fn test_cross_validator_run() {
+    let xseq = vec![
+        vec![Attribute::new("walk", 1.0), Attribute::new("shop", 0.5)],
+        vec![Attribute::new("walk", 1.0)],
+        vec![Attribute::new("walk", 1.0), Attribute::new("clean", 0.5)],
+        vec![Attribute::new("shop", 0.5), Attribute::new("clean", 0.5)],
+        vec![Attribute::new("walk", 0.5), Attribute::new("clean", 1.0)],
+        vec![Attribute::new("clean", 1.0), Attribute::new("shop", 0.1)],
+        vec![Attribute::new("walk", 1.0), Attribute::new("shop", 0.5)],
+        vec![],
+        vec![Attribute::new("clean", 1.0)],
+    ];
+    let yseq = ["sunny", "sunny", "sunny", "rainy", "rainy", "rainy", "sunny", "sunny", "rainy"];
+
+    // Three identical copies of the same sequence, so every held-out fold's training data
+    // still contains the exact sequence it is evaluated against.
+    let mut validator = CrossValidator::new(3, Algorithm::LBFGS).unwrap();
+    for _ in 0..3 {
+        validator.append(&xseq, &yseq);
+    }
+    let evaluation = validator.run().unwrap();
+    assert!(evaluation.item_accuracy() > 0.0);
+}
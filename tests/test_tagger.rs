@@ -1,6 +1,8 @@
 extern crate crfsuite;
 
-use crfsuite::{Model, Attribute};
+use std::sync::Arc;
+
+use crfsuite::{Model, Attribute, TaggerPool};
 
 #[test]
 fn test_open_model() {
@@ -21,6 +23,22 @@ fn test_dump_model() {
     model.dump_file("tests/model.dump").unwrap();
 }
 
+#[test]
+fn test_model_info_state_features_match_attributes() {
+    let model = Model::from_file("tests/model.crfsuite").unwrap();
+    let attrs: std::collections::HashSet<String> = model.attributes().unwrap().into_iter().collect();
+    let info = model.info().unwrap();
+    assert!(!info.state_features.is_empty());
+    for (attr, _label) in info.state_features.keys() {
+        assert!(
+            attrs.contains(attr),
+            "attribute `{}` from state_features is not a known attribute (stray delimiter char?)",
+            attr
+        );
+    }
+    assert!(model.state_weight("walk", "sunny").is_ok());
+}
+
 
 #[test]
 fn test_create_model_from_memory() {
@@ -46,6 +64,33 @@ fn test_create_model_from_memory() {
     tagger.marginal("sunny", 1i32).unwrap();
 }
 
+#[test]
+fn test_tag_nbest() {
+    let model_memory = include_bytes!("model.crfsuite");
+    let model = Model::from_memory(&model_memory[..]).unwrap();
+    let mut tagger = model.tagger().unwrap();
+    let xseq = vec![
+        vec![Attribute::new("walk", 1.0), Attribute::new("shop", 0.5)],
+        vec![Attribute::new("walk", 1.0)],
+    ];
+    let best = tagger.tag(&xseq).unwrap();
+    let nbest = tagger.tag_nbest(&xseq, 3).unwrap();
+    assert!(!nbest.is_empty());
+    assert_eq!(nbest[0].0, best);
+    for pair in nbest.windows(2) {
+        assert!(pair[0].1 >= pair[1].1);
+    }
+}
+
+#[test]
+fn test_tag_nbest_nan_attribute_does_not_panic() {
+    let model_memory = include_bytes!("model.crfsuite");
+    let model = Model::from_memory(&model_memory[..]).unwrap();
+    let mut tagger = model.tagger().unwrap();
+    let xseq = vec![vec![Attribute::new("walk", f64::NAN)]];
+    let _ = tagger.tag_nbest(&xseq, 2);
+}
+
 #[test]
 fn test_tag() {
     let model = Model::from_file("tests/model.crfsuite").unwrap();
@@ -68,3 +113,46 @@ fn test_tag() {
     tagger.probability(&yseq).unwrap();
     tagger.marginal("sunny", 1i32).unwrap();
 }
+
+#[test]
+fn test_model_tag_batch_matches_sequential_tag() {
+    let model = Model::from_file("tests/model.crfsuite").unwrap();
+    let xseqs = vec![
+        vec![Attribute::new("walk", 1.0), Attribute::new("shop", 0.5)],
+        vec![Attribute::new("walk", 1.0)],
+        vec![Attribute::new("clean", 1.0)],
+    ];
+    let sequences: Vec<_> = xseqs.iter().map(|item| vec![item.clone()]).collect();
+
+    let batch_results = model.tag_batch(&sequences).unwrap();
+
+    let mut tagger = model.tagger().unwrap();
+    let sequential_results: Vec<_> = sequences
+        .iter()
+        .map(|xseq| tagger.tag(xseq).unwrap())
+        .collect();
+
+    assert_eq!(batch_results, sequential_results);
+}
+
+#[test]
+fn test_tagger_pool_tag_batch_matches_sequential_tag() {
+    let model = Arc::new(Model::from_file("tests/model.crfsuite").unwrap());
+    let xseqs = vec![
+        vec![Attribute::new("walk", 1.0), Attribute::new("shop", 0.5)],
+        vec![Attribute::new("walk", 1.0)],
+        vec![Attribute::new("clean", 1.0)],
+    ];
+    let sequences: Vec<_> = xseqs.iter().map(|item| vec![item.clone()]).collect();
+
+    let pool = TaggerPool::new(model.clone());
+    let batch_results = pool.tag_batch(&sequences).unwrap();
+
+    let mut tagger = model.tagger().unwrap();
+    let sequential_results: Vec<_> = sequences
+        .iter()
+        .map(|xseq| tagger.tag(xseq).unwrap())
+        .collect();
+
+    assert_eq!(batch_results, sequential_results);
+}